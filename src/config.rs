@@ -12,6 +12,8 @@ pub struct Config {
     pub fee_payer: Pubkey,
     pub default_keypair_path: String,
     pub dry_run: bool,
+    pub priority_fee: Option<u64>,
+    pub compute_unit_limit: Option<u32>,
 }
 
 impl Config {
@@ -2,6 +2,7 @@ use clap::{
     self, crate_description, crate_name, crate_version, value_t, value_t_or_exit, App, AppSettings,
     Arg, ArgGroup, ArgMatches, SubCommand, Values,
 };
+use console::user_attended;
 use spl_associated_token_account::{
     self, create_associated_token_account, get_associated_token_address,
 };
@@ -22,12 +23,12 @@ use solana_account_decoder::{
 };
 use solana_clap_utils::{
     fee_payer::fee_payer_arg,
-    input_parsers::{pubkey_of, pubkey_of_signer, value_of},
+    input_parsers::{commitment_of, pubkey_of, pubkey_of_signer, value_of},
     input_validators::{
-        is_parsable, is_url, is_url_or_moniker, is_valid_pubkey, is_valid_signer,
+        is_parsable, is_pubkey, is_url, is_url_or_moniker, is_valid_pubkey, is_valid_signer,
         normalize_to_url_if_moniker,
     },
-    keypair::{signer_from_path, CliSignerInfo},
+    keypair::signer_from_path,
     memo::memo_arg,
 };
 use solana_cli_output::{CliSignature, OutputFormat};
@@ -39,11 +40,14 @@ use solana_client::{
 use solana_remote_wallet::remote_wallet::RemoteWalletManager;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
     instruction::Instruction,
     message::Message,
     native_token::lamports_to_sol,
     program_pack::Pack,
     pubkey::Pubkey,
+    signature::Signature,
     signer::{keypair::Keypair, Signer},
     system_instruction, system_program,
     transaction::Transaction,
@@ -52,19 +56,32 @@ use spl_token::{
     self,
     state::{Account, Mint},
 };
-use std::{fmt::Display, process::exit, str::FromStr, sync::Arc};
+use std::{fmt::Display, process::exit, str::FromStr, sync::Arc, thread, time::Duration};
+use url::Url;
 
 pub mod config;
 use crate::config::Config;
 
 pub mod output;
-use output::{println_display, CliMetadata, CliMint, CliTokenAmount, UiMetadata};
+use output::{
+    println_display, CliAddressValidation, CliDoctor, CliMetadata, CliMetadataJson, CliMetadataPda,
+    CliMetadataUpdate, CliMetadataUpdateBatch, CliMint, CliTokenAmount, UiCreator, UiDoctorCheck,
+    UiMetadata, UiMetadataUpdate, UiMetadataUpdateResult,
+};
 
 type Error = Box<dyn std::error::Error>;
 type CommandResult = Result<Option<(u64, Vec<Vec<Instruction>>)>, Error>;
 
 // CONSTANTS
 
+// Upper bound on fetching off-chain metadata so a stalled host can't hang the cli.
+const METADATA_JSON_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Shown after the help for metadata-update and metadata-update-batch, which don't take --immutable.
+const METADATA_UPDATE_AFTER_HELP: &str = "Metadata can't be made immutable with an update: \
+    the update instruction in the token metadata program version used by this cli does not \
+    support is_mutable. Pass --immutable when creating metadata instead.";
+
 // INPUT VALIDATORS
 
 fn is_mint_decimals(string: String) -> Result<(), String> {
@@ -96,6 +113,49 @@ where
         })
 }
 
+fn is_concurrency(string: String) -> Result<(), String> {
+    string
+        .parse::<usize>()
+        .map_err(|e| {
+            format!(
+                "Unable to parse concurrency, provided: {}, err: {}",
+                string, e
+            )
+        })
+        .and_then(|v| {
+            if v < 1 {
+                Err(format!("Concurrency must be at least 1, provided: {}", v))
+            } else {
+                Ok(())
+            }
+        })
+}
+
+// Arweave wallet addresses are the base64url encoded (without padding) sha256 hash
+// of the wallet's public key modulus, so they always decode to 32 bytes.
+fn is_valid_arweave_address<T>(address: T) -> Result<(), String>
+where
+    T: AsRef<str> + Display,
+{
+    base64::decode_config(address.as_ref(), base64::URL_SAFE_NO_PAD)
+        .map_err(|e| {
+            format!(
+                "Unable to decode base64url, provided: {}, err: {}",
+                address, e
+            )
+        })
+        .and_then(|bytes| {
+            if bytes.len() != 32 {
+                Err(format!(
+                    "Arweave addresses must decode to 32 bytes, provided: {} bytes",
+                    bytes.len()
+                ))
+            } else {
+                Ok(())
+            }
+        })
+}
+
 // Checks to make sure creator shares sum to 100.
 fn validate_creator_shares(creators: &Vec<Creator>) -> Result<(), clap::Error> {
     let share_sum: u64 = creators.iter().map(|c| c.share as u64).sum();
@@ -177,6 +237,51 @@ impl FromArgMatches<Data> for Data {
     }
 }
 
+#[derive(Clone, Default)]
+struct MetadataUpdate {
+    new_update_authority: Option<Pubkey>,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+    seller_fee_basis_points: Option<u16>,
+    creators: Option<Vec<Creator>>,
+    primary_sale_happened: Option<bool>,
+}
+
+impl FromArgMatches<MetadataUpdate> for MetadataUpdate {
+    fn from_argmatches(arg_matches: &ArgMatches) -> Self {
+        Self {
+            new_update_authority: pubkey_of(arg_matches, "new_update_authority"),
+            name: arg_matches.value_of("name").map(|v| v.to_string()),
+            symbol: arg_matches.value_of("symbol").map(|v| v.to_string()),
+            uri: arg_matches.value_of("uri").map(|v| v.to_string()),
+            seller_fee_basis_points: value_of::<u16>(arg_matches, "seller_fee_basis_points"),
+            creators: get_creators_vec(arg_matches.values_of("creators")),
+            primary_sale_happened: arg_matches
+                .is_present("primary_sale_happened")
+                .then(|| true),
+        }
+    }
+}
+
+// Accepts either a json list of mint addresses or one address per line.
+fn parse_mints(content: &str) -> Result<Vec<Pubkey>, Error> {
+    let values: Vec<String> = if content.trim_start().starts_with('[') {
+        serde_json::from_str(content)?
+    } else {
+        content
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_string())
+            .collect()
+    };
+    values
+        .iter()
+        .map(|v| Pubkey::from_str(v).map_err(|e| format!("Invalid mint {}: {}", v, e).into()))
+        .collect()
+}
+
 struct MintData {
     token: Pubkey,
     mint_authority: Pubkey,
@@ -219,6 +324,33 @@ impl FromArgMatchesConfig<MintData> for MintData {
     }
 }
 
+impl From<&MetadataUpdate> for UiMetadataUpdate {
+    fn from(update: &MetadataUpdate) -> Self {
+        Self {
+            new_update_authority: update.new_update_authority.map(|a| a.to_string()),
+            name: update.name.clone(),
+            symbol: update.symbol.clone(),
+            uri: update.uri.clone(),
+            seller_fee_basis_points: update.seller_fee_basis_points.map(|b| b.to_string()),
+            creators: update
+                .creators
+                .as_ref()
+                .map(|c_vec| c_vec.iter().map(|c| UiCreator::from(c)).collect()),
+            primary_sale_happened: update.primary_sale_happened,
+        }
+    }
+}
+
+impl From<(Pubkey, Result<Signature, Error>)> for UiMetadataUpdateResult {
+    fn from((mint, result): (Pubkey, Result<Signature, Error>)) -> Self {
+        Self {
+            mint: mint.to_string(),
+            signature: result.as_ref().ok().map(|s| s.to_string()),
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+}
+
 fn parse_cli_metadata(address: Pubkey, metadata: Metadata) -> CliMetadata {
     CliMetadata {
         address: address.to_string(),
@@ -333,6 +465,66 @@ fn get_signer(
     })
 }
 
+// Prepends compute budget instructions when a priority fee or compute unit limit
+// was provided so that transactions can land during periods of congestion.
+fn with_compute_budget(config: &Config, instructions: Vec<Instruction>) -> Vec<Instruction> {
+    let mut budget_instructions = Vec::new();
+    if let Some(compute_unit_limit) = config.compute_unit_limit {
+        budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+            compute_unit_limit,
+        ));
+    }
+    if let Some(priority_fee) = config.priority_fee {
+        budget_instructions.push(ComputeBudgetInstruction::set_compute_unit_price(
+            priority_fee,
+        ));
+    }
+    budget_instructions.extend(instructions);
+    budget_instructions
+}
+
+// Prepends any compute budget instructions, checks that the fee payer can cover the rent
+// and fees and signs with whichever of the signers the message requires. The fee is quoted
+// by the rpc node for the whole message, so it includes any priority fee.
+fn build_transaction(
+    config: &Config,
+    signers: &[Box<dyn Signer>],
+    instructions: Vec<Instruction>,
+    minimum_balance_for_rent_exemption: u64,
+    recent_blockhash: Hash,
+) -> Result<Transaction, Error> {
+    let instructions = with_compute_budget(config, instructions);
+    let message =
+        Message::new_with_blockhash(&instructions, Some(&config.fee_payer), &recent_blockhash);
+    let fee = config.rpc_client.get_fee_for_message(&message)?;
+    check_fee_payer_balance(config, minimum_balance_for_rent_exemption + fee)?;
+
+    let signer_keys = message.signer_keys();
+    let message_signers: Vec<&dyn Signer> = signers
+        .iter()
+        .filter(|s| signer_keys.contains(&&s.pubkey()))
+        .map(|s| s.as_ref())
+        .collect();
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.try_sign(&message_signers, recent_blockhash)?;
+    Ok(transaction)
+}
+
+fn send_transaction(
+    config: &Config,
+    transaction: &Transaction,
+    no_wait: bool,
+) -> Result<Signature, Error> {
+    let signature = if no_wait {
+        config.rpc_client.send_transaction(transaction)?
+    } else {
+        config
+            .rpc_client
+            .send_and_confirm_transaction_with_spinner(transaction)?
+    };
+    Ok(signature)
+}
+
 pub(crate) fn check_fee_payer_balance(config: &Config, required_balance: u64) -> Result<(), Error> {
     let balance = config.rpc_client.get_balance(&config.fee_payer)?;
     if balance < required_balance {
@@ -369,6 +561,14 @@ fn update_authority_arg<'a, 'b>() -> Arg<'a, 'b> {
         .help("Specify the update authority address. Defaults to the client keypair address.")
 }
 
+fn immutable_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("immutable")
+        .long("immutable")
+        .value_name("IMMUTABLE")
+        .takes_value(false)
+        .help("Prohibit future metadata updates")
+}
+
 fn mint_address_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("mint_address")
         .value_name("MINT_ADDRESS")
@@ -453,13 +653,6 @@ impl MetadataArgs for App<'_, '_> {
                 .validator(is_url)
                 .help("Specify the URI for the mint."),
         )
-        .arg(
-            Arg::with_name("immutable")
-                .long("immutable")
-                .value_name("IMMUTABLE")
-                .takes_value(false)
-                .help("Prohibit future metadata updates"),
-        )
         .arg(
             Arg::with_name("seller_fee_basis_points")
                 .long("seller-fee-basis-points")
@@ -482,6 +675,63 @@ impl MetadataArgs for App<'_, '_> {
             separated by spaces.",
                 ),
         )
+        .arg(
+            Arg::with_name("priority_fee")
+                .long("priority-fee")
+                .value_name("MICROLAMPORTS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help("Specify a compute unit price in micro-lamports to prioritize transactions."),
+        )
+        .arg(
+            Arg::with_name("compute_unit_limit")
+                .long("compute-unit-limit")
+                .value_name("COMPUTE_UNITS")
+                .takes_value(true)
+                .validator(is_parsable::<u32>)
+                .help("Specify the maximum compute units each transaction may consume."),
+        )
+    }
+}
+
+trait MetadataUpdateArgs {
+    fn metadata_update_args(self) -> Self;
+}
+
+impl MetadataUpdateArgs for App<'_, '_> {
+    fn metadata_update_args(self) -> Self {
+        self.arg(update_authority_arg())
+            .arg(
+                Arg::with_name("new_update_authority")
+                    .long("new-update-authority")
+                    .value_name("NEW_UPDATE_AUTHORITY_ADDRESS")
+                    .validator(is_valid_pubkey)
+                    .takes_value(true)
+                    .help("Specify the new update authority address."),
+            )
+            .arg(
+                Arg::with_name("primary_sale_happened")
+                    .long("primary-sale-happened")
+                    .value_name("PRIMARY_SALE_HAPPENED")
+                    .takes_value(false)
+                    .help("indicateS primary sale has happened."),
+            )
+            .metadata_args()
+            .after_help(METADATA_UPDATE_AFTER_HELP)
+            .group(
+                ArgGroup::with_name("update_values")
+                    .args(&vec![
+                        "new_update_authority",
+                        "name",
+                        "symbol",
+                        "uri",
+                        "seller_fee_basis_points",
+                        "creators",
+                        "primary_sale_happened",
+                    ])
+                    .required(true)
+                    .multiple(true),
+            )
     }
 }
 
@@ -535,6 +785,21 @@ fn get_app() -> App<'static, 'static> {
                 .possible_values(&["json", "json-compact"])
                 .help("Return information in specified output format."),
         )
+        .arg(
+            Arg::with_name("pretty")
+                .long("pretty")
+                .takes_value(false)
+                .global(true)
+                .conflicts_with("no_pretty")
+                .help("Indent json output. Default when writing to a terminal."),
+        )
+        .arg(
+            Arg::with_name("no_pretty")
+                .long("no-pretty")
+                .takes_value(false)
+                .global(true)
+                .help("Print json output without indentation. Default when output is piped."),
+        )
         .arg(
             Arg::with_name("dry_run")
                 .long("dry-run")
@@ -542,6 +807,16 @@ fn get_app() -> App<'static, 'static> {
                 .global(true)
                 .help("Simulate transaction instead of executing."),
         )
+        .arg(
+            Arg::with_name("commitment")
+                .long("commitment")
+                .value_name("COMMITMENT_LEVEL")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["processed", "confirmed", "finalized"])
+                .default_value("confirmed")
+                .help("Return information at the selected commitment level."),
+        )
         .arg(fee_payer_arg().global(true))
         .subcommand(
             SubCommand::with_name("mint-info")
@@ -553,49 +828,76 @@ fn get_app() -> App<'static, 'static> {
                 .about("Query details of a Metadata account by address.")
                 .arg(generic_address_arg()),
         )
+        .subcommand(
+            SubCommand::with_name("metadata-pda")
+                .about(
+                    "Derive the metadata and master edition account addresses \
+                    for a mint without making any rpc calls.",
+                )
+                .arg(mint_address_arg().index(1).required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("metadata-json")
+                .about(
+                    "Fetch and display the off-chain json referenced by a metadata account's uri.",
+                )
+                .arg(generic_address_arg()),
+        )
         .subcommand(SubCommand::with_name("filter").arg(generic_address_arg()))
+        .subcommand(
+            SubCommand::with_name("doctor")
+                .about("Check that the fee payer keypair, RPC node and balance are usable."),
+        )
+        .subcommand(
+            SubCommand::with_name("validate-address")
+                .about("Check whether a value is a valid Solana pubkey or Arweave wallet address.")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .help("The address to validate."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("metadata-create")
                 .about("Create metadata account for existing token mint.")
                 .arg(mint_address_arg())
                 .arg(update_authority_arg())
+                .arg(immutable_arg())
                 .metadata_args(),
         )
         .subcommand(
             SubCommand::with_name("metadata-update")
                 .about("Update an existing metadata account.")
                 .arg(generic_address_arg())
-                .arg(update_authority_arg())
+                .metadata_update_args(),
+        )
+        .subcommand(
+            SubCommand::with_name("metadata-update-batch")
+                .about("Apply the same update to the metadata accounts of a list of mints.")
                 .arg(
-                    Arg::with_name("new_update_authority")
-                        .long("new-update-authority")
-                        .value_name("NEW_UPDATE_AUTHORITY_ADDRESS")
-                        .validator(is_valid_pubkey)
+                    Arg::with_name("mints_file")
+                        .value_name("MINTS_FILE")
                         .takes_value(true)
-                        .help("Specify the new update authority address."),
+                        .index(1)
+                        .required(true)
+                        .help(
+                            "Path to a file with one mint address per line \
+                            or a json list of mint addresses.",
+                        ),
                 )
                 .arg(
-                    Arg::with_name("primary_sale_happened")
-                        .long("primary-sale-happened")
-                        .value_name("PRIMARY_SALE_HAPPENED")
-                        .takes_value(false)
-                        .help("indicateS primary sale has happened."),
+                    Arg::with_name("concurrency")
+                        .long("concurrency")
+                        .value_name("CONCURRENCY")
+                        .takes_value(true)
+                        .validator(is_concurrency)
+                        .default_value("1")
+                        .help("Number of update transactions to send and confirm at a time."),
                 )
-                .metadata_args()
-                .group(
-                    ArgGroup::with_name("update_values")
-                        .args(&vec![
-                            "new_update_authority",
-                            "name",
-                            "symbol",
-                            "uri",
-                            "seller_fee_basis_points",
-                            "creators",
-                            "primary_sale_happened",
-                        ])
-                        .required(true)
-                        .multiple(true),
-                ),
+                .metadata_update_args(),
         )
         .subcommand(
             SubCommand::with_name("nft-create")
@@ -606,6 +908,7 @@ fn get_app() -> App<'static, 'static> {
                 .mint_args()
                 .metadata_args()
                 .arg(update_authority_arg())
+                .arg(immutable_arg())
                 .arg(
                     Arg::with_name("max_supply")
                         .long("max-supply")
@@ -648,6 +951,7 @@ async fn main() {
     let (sub_command, sub_matches) = app_matches.subcommand();
     let matches = sub_matches.unwrap();
 
+    let mut keypair_error: Option<String> = None;
     let config = {
         let cli_config = if let Some(config_file) = matches.value_of("config_file") {
             solana_cli_config::Config::load(config_file).unwrap_or_default()
@@ -660,29 +964,46 @@ async fn main() {
                 .unwrap_or(&cli_config.json_rpc_url),
         );
 
-        let (signer, fee_payer) = signer_from_path(
-            matches,
-            matches
-                .value_of("fee_payer")
-                .unwrap_or(&cli_config.keypair_path),
-            "fee_payer",
-            &mut wallet_manager,
-        )
-        .map(|s| {
-            let p = s.pubkey();
-            (s, p)
-        })
-        .unwrap_or_else(|e| {
-            eprintln!("error: {}", e);
-            exit(1);
-        });
-        bulk_signers.push(signer);
+        // Offline commands never use the fee payer, so they can run without a keypair.
+        let fee_payer = if matches!(sub_command, "metadata-pda" | "validate-address") {
+            Pubkey::default()
+        } else {
+            match signer_from_path(
+                matches,
+                matches
+                    .value_of("fee_payer")
+                    .unwrap_or(&cli_config.keypair_path),
+                "fee_payer",
+                &mut wallet_manager,
+            ) {
+                Ok(signer) => {
+                    let fee_payer = signer.pubkey();
+                    bulk_signers.push(signer);
+                    fee_payer
+                }
+                // doctor reports an unusable keypair as a failed check instead of exiting.
+                Err(e) if sub_command == "doctor" => {
+                    keypair_error = Some(e.to_string());
+                    Pubkey::default()
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    exit(1);
+                }
+            }
+        };
 
         let verbose = matches.is_present("verbose");
+        let pretty = if matches.is_present("no_pretty") {
+            false
+        } else {
+            matches.is_present("pretty") || user_attended()
+        };
         let output_format = matches
             .value_of("output_format")
             .map(|value| match value {
-                "json" => OutputFormat::Json,
+                "json" if pretty => OutputFormat::Json,
+                "json" => OutputFormat::JsonCompact,
                 "json-compact" => OutputFormat::JsonCompact,
                 _ => unreachable!(),
             })
@@ -693,13 +1014,17 @@ async fn main() {
             });
 
         let dry_run = matches.is_present("dry_run");
+        let commitment =
+            commitment_of(matches, "commitment").unwrap_or_else(CommitmentConfig::confirmed);
 
         Config {
-            rpc_client: RpcClient::new_with_commitment(json_rpc_url, CommitmentConfig::confirmed()),
+            rpc_client: RpcClient::new_with_commitment(json_rpc_url, commitment),
             output_format,
             fee_payer,
             default_keypair_path: cli_config.keypair_path,
             dry_run,
+            priority_fee: value_of::<u64>(matches, "priority_fee"),
+            compute_unit_limit: value_of::<u32>(matches, "compute_unit_limit"),
         }
     };
 
@@ -714,10 +1039,23 @@ async fn main() {
             let address = pubkey_of(arg_matches, "address").unwrap();
             command_metadata_info(&config, address)
         }
+        ("metadata-pda", Some(arg_matches)) => {
+            let mint_address = pubkey_of(arg_matches, "mint_address").unwrap();
+            command_metadata_pda(&config, mint_address)
+        }
+        ("metadata-json", Some(arg_matches)) => {
+            let address = pubkey_of(arg_matches, "address").unwrap();
+            command_metadata_json(&config, address).await
+        }
         ("filter", Some(arg_matches)) => {
             let address = pubkey_of(arg_matches, "address").unwrap();
             get_filtered_program_accounts(&config, address)
         }
+        ("doctor", Some(_)) => command_doctor(&config, keypair_error.take()),
+        ("validate-address", Some(arg_matches)) => {
+            let address = arg_matches.value_of("address").unwrap();
+            command_validate_address(&config, address)
+        }
         ("metadata-create", Some(arg_matches)) => {
             let mint_address = pubkey_of(arg_matches, "mint_address").unwrap();
             let update_authority =
@@ -739,31 +1077,38 @@ async fn main() {
             let address = pubkey_of(arg_matches, "address").unwrap();
             let update_authority =
                 config.pubkey_or_default(arg_matches, "update_authority", &mut wallet_manager);
-            let new_update_authority = pubkey_of(arg_matches, "new_update_authority");
-
-            let name = arg_matches.value_of("name").map(|v| v.to_string());
-            let symbol = arg_matches.value_of("symbol").map(|v| v.to_string());
-            let uri = arg_matches.value_of("uri").map(|v| v.to_string());
-            let seller_fee_basis_points = value_of::<u16>(arg_matches, "seller_fee_basis_points");
-            let creators = get_creators_vec(arg_matches.values_of("creators"));
-
-            let primary_sale_happened = arg_matches
-                .is_present("primary_sale_happened")
-                .then(|| true);
+            let update = MetadataUpdate::from_argmatches(&arg_matches);
 
             command_metadata_update_account(
                 &config,
+                &bulk_signers,
                 address,
                 update_authority,
-                new_update_authority,
-                name,
-                symbol,
-                uri,
-                seller_fee_basis_points,
-                creators,
-                primary_sale_happened,
+                update,
+                no_wait,
             )
         }
+        ("metadata-update-batch", Some(arg_matches)) => {
+            let mints_file = arg_matches.value_of("mints_file").unwrap();
+            let update_authority =
+                config.pubkey_or_default(arg_matches, "update_authority", &mut wallet_manager);
+            let update = MetadataUpdate::from_argmatches(&arg_matches);
+            let concurrency = value_t_or_exit!(arg_matches, "concurrency", usize);
+
+            std::fs::read_to_string(mints_file)
+                .map_err(|e| format!("Unable to read {}: {}", mints_file, e).into())
+                .and_then(|content| parse_mints(&content))
+                .and_then(|mints| {
+                    command_metadata_update_batch(
+                        &config,
+                        &bulk_signers,
+                        mints,
+                        update_authority,
+                        update,
+                        concurrency,
+                    )
+                })
+        }
         ("nft-create", Some(arg_matches)) => {
             let (signer, mint_data) =
                 MintData::from_argmatches(&arg_matches, Some(&config), &mut wallet_manager);
@@ -805,30 +1150,17 @@ async fn main() {
     // instructions can be processed together in separate transactions atomically.
     .and_then(|transaction_info| {
         if let Some((minimum_balance_for_rent_exemption, instruction_batches)) = transaction_info {
-            let fee_payer = Some(&config.fee_payer);
-            let signer_info = CliSignerInfo {
-                signers: bulk_signers,
-            };
-            let (recent_blockhash, fee_calculator) = config.rpc_client.get_recent_blockhash()?;
+            let recent_blockhash = config.rpc_client.get_latest_blockhash()?;
             for instructions in instruction_batches {
-                let message = Message::new(&instructions, fee_payer);
-                check_fee_payer_balance(
+                let transaction = build_transaction(
                     &config,
-                    minimum_balance_for_rent_exemption + fee_calculator.calculate_fee(&message),
+                    &bulk_signers,
+                    instructions,
+                    minimum_balance_for_rent_exemption,
+                    recent_blockhash,
                 )?;
-                let signers = signer_info.signers_for_message(&message);
-                let mut transaction = Transaction::new_unsigned(message);
-
-                transaction.try_sign(&signers, recent_blockhash)?;
-                let signature = if no_wait {
-                    config.rpc_client.send_transaction(&transaction)?
-                } else {
-                    config
-                        .rpc_client
-                        .send_and_confirm_transaction_with_spinner(&transaction)?
-                };
                 let signature = CliSignature {
-                    signature: signature.to_string(),
+                    signature: send_transaction(&config, &transaction, no_wait)?.to_string(),
                 };
                 println!("{}", config.output_format.formatted_string(&signature));
             }
@@ -895,6 +1227,177 @@ fn command_metadata_info(config: &Config, address: Pubkey) -> CommandResult {
     Ok(None)
 }
 
+fn command_doctor(config: &Config, keypair_error: Option<String>) -> CommandResult {
+    let checks = doctor_checks(config, keypair_error);
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    let cli_doctor = CliDoctor { checks };
+    println!("{}", &config.output_format.formatted_string(&cli_doctor));
+
+    if failed > 0 {
+        Err(format!("{} check(s) failed.", failed).into())
+    } else {
+        Ok(None)
+    }
+}
+
+// Runs each check in turn, continuing past failures so that all of the problems
+// are reported at once. keypair_error is the error from loading the fee payer
+// keypair when the config was built, if there was one.
+fn doctor_checks(config: &Config, keypair_error: Option<String>) -> Vec<UiDoctorCheck> {
+    let keypair_passed = keypair_error.is_none();
+    let mut checks = vec![match keypair_error {
+        None => UiDoctorCheck {
+            name: "Keypair".to_string(),
+            passed: true,
+            message: format!("Loaded fee payer {}", config.fee_payer),
+        },
+        Some(error) => UiDoctorCheck {
+            name: "Keypair".to_string(),
+            passed: false,
+            message: format!(
+                "Unable to load fee payer keypair: {}. Create one with `solana-keygen new`, \
+                or point --fee-payer or the keypair_path in your config file at an existing one.",
+                error
+            ),
+        },
+    }];
+
+    let rpc_check = match config.rpc_client.get_version() {
+        Ok(version) => UiDoctorCheck {
+            name: "RPC".to_string(),
+            passed: true,
+            message: format!("Reachable, solana-core {}", version.solana_core),
+        },
+        Err(error) => UiDoctorCheck {
+            name: "RPC".to_string(),
+            passed: false,
+            message: format!(
+                "Unable to reach node: {}. Check --url or the json_rpc_url in your config file.",
+                error
+            ),
+        },
+    };
+    let rpc_passed = rpc_check.passed;
+    checks.push(rpc_check);
+
+    if keypair_passed && rpc_passed {
+        checks.push(match config.rpc_client.get_balance(&config.fee_payer) {
+            Ok(balance) if balance > 0 => UiDoctorCheck {
+                name: "Balance".to_string(),
+                passed: true,
+                message: format!("{} SOL", lamports_to_sol(balance)),
+            },
+            Ok(_) => UiDoctorCheck {
+                name: "Balance".to_string(),
+                passed: false,
+                message: format!(
+                    "Fee payer {} has no SOL. Fund it before creating accounts, \
+                    e.g. with `solana airdrop 1` on devnet.",
+                    config.fee_payer
+                ),
+            },
+            Err(error) => UiDoctorCheck {
+                name: "Balance".to_string(),
+                passed: false,
+                message: format!("Unable to fetch balance: {}", error),
+            },
+        });
+    }
+
+    checks
+}
+
+fn command_validate_address(config: &Config, address: &str) -> CommandResult {
+    let solana_pubkey = is_pubkey(address);
+    let arweave_address = is_valid_arweave_address(address);
+    let is_valid = solana_pubkey.is_ok() || arweave_address.is_ok();
+
+    let cli_address_validation = CliAddressValidation {
+        address: address.to_string(),
+        solana_pubkey: solana_pubkey.into(),
+        arweave_address: arweave_address.into(),
+    };
+    println!(
+        "{}",
+        &config
+            .output_format
+            .formatted_string(&cli_address_validation)
+    );
+
+    if is_valid {
+        Ok(None)
+    } else {
+        Err(format!(
+            "{} is neither a valid Solana pubkey nor a valid Arweave address.",
+            address
+        )
+        .into())
+    }
+}
+
+// The metadata account is a program derived address with seeds
+// ["metadata", token metadata program id, mint], which is why errors like
+// AccountNotFound can refer to a pubkey that doesn't appear anywhere in the inputs.
+fn command_metadata_pda(config: &Config, mint_address: Pubkey) -> CommandResult {
+    let cli_metadata_pda = CliMetadataPda {
+        mint: mint_address.to_string(),
+        metadata: Metadata::calc_associated_address(&mint_address, None).to_string(),
+        master_edition: Mint::calc_master_edition(&mint_address).to_string(),
+    };
+    println!(
+        "{}",
+        &config.output_format.formatted_string(&cli_metadata_pda)
+    );
+    Ok(None)
+}
+
+// Metadata uris are stored padded with null bytes. Only http(s) uris can be fetched, so
+// e.g. ipfs:// or ar:// uris are rejected here rather than failing inside the http client.
+fn parse_metadata_uri(address: &Pubkey, uri: &str) -> Result<(String, Url), String> {
+    let uri = uri.trim_end_matches(char::from(0)).to_string();
+    if uri.is_empty() {
+        return Err(format!("Metadata account {} does not have a uri.", address));
+    }
+    let url = Url::parse(&uri).map_err(|e| format!("Unable to parse uri {}: {}", uri, e))?;
+    match url.scheme() {
+        "http" | "https" => Ok((uri, url)),
+        scheme => Err(format!(
+            "Unable to fetch uri {}: {}:// uris are not supported, only http and https.",
+            uri, scheme
+        )),
+    }
+}
+
+// Follows the uri stored in the metadata account, e.g. https://arweave.net/{id} or a
+// manifest path, and parses the response body as json.
+async fn command_metadata_json(config: &Config, address: Pubkey) -> CommandResult {
+    let (address, metadata) = Metadata::fetch_and_parse(config, &address)?;
+    let (uri, url) = parse_metadata_uri(&address, &metadata.data.uri)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(METADATA_JSON_TIMEOUT)
+        .build()?;
+    let response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Request to {} failed with status {}",
+            uri,
+            response.status()
+        )
+        .into());
+    }
+    let body = response.text().await?;
+    let json: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|e| format!("Unable to parse json from {}: {}", uri, e))?;
+
+    let cli_metadata_json = CliMetadataJson { uri, json };
+    println!(
+        "{}",
+        &config.output_format.formatted_string(&cli_metadata_json)
+    );
+    Ok(None)
+}
+
 fn command_metadata_create(
     config: &Config,
     mint_address: Pubkey,
@@ -951,19 +1454,71 @@ fn command_metadata_create(
     )))
 }
 
+// Sends the update itself rather than returning instructions to main so that it can
+// report the mint and just the fields that changed along with the signature.
 fn command_metadata_update_account(
     config: &Config,
+    signers: &[Box<dyn Signer>],
     address: Pubkey,
     update_authority: Pubkey,
-    new_update_authority: Option<Pubkey>,
-    name: Option<String>,
-    symbol: Option<String>,
-    uri: Option<String>,
-    seller_fee_basis_points: Option<u16>,
-    creators: Option<Vec<Creator>>,
-    primary_sale_happened: Option<bool>,
+    update: MetadataUpdate,
+    no_wait: bool,
 ) -> CommandResult {
-    let (metadata_address, mut metadata) = Metadata::fetch_and_parse(config, &address)?;
+    let (metadata_address, metadata) = Metadata::fetch_and_parse(config, &address)?;
+    let mint = metadata.mint;
+    let updated = UiMetadataUpdate::from(&update);
+    let instructions =
+        metadata_update_instructions(config, metadata_address, metadata, update_authority, update)?;
+
+    let minimum_balance_for_rent_exemption = config
+        .rpc_client
+        .get_minimum_balance_for_rent_exemption(MAX_METADATA_LEN)?;
+    let recent_blockhash = config.rpc_client.get_latest_blockhash()?;
+    let transaction = build_transaction(
+        config,
+        signers,
+        instructions,
+        minimum_balance_for_rent_exemption,
+        recent_blockhash,
+    )?;
+    let signature = send_transaction(config, &transaction, no_wait)?;
+
+    // The update has already landed, so a failed status lookup only costs the slot.
+    let slot = config
+        .rpc_client
+        .get_signature_statuses(&[signature])
+        .ok()
+        .and_then(|statuses| statuses.value[0].as_ref().map(|status| status.slot));
+    let cli_metadata_update = CliMetadataUpdate {
+        signature: signature.to_string(),
+        slot,
+        mint: mint.to_string(),
+        updated,
+    };
+    println!(
+        "{}",
+        config.output_format.formatted_string(&cli_metadata_update)
+    );
+    Ok(None)
+}
+
+fn metadata_update_instructions(
+    config: &Config,
+    metadata_address: Pubkey,
+    mut metadata: Metadata,
+    update_authority: Pubkey,
+    update: MetadataUpdate,
+) -> Result<Vec<Instruction>, Error> {
+    let MetadataUpdate {
+        new_update_authority,
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points,
+        creators,
+        primary_sale_happened,
+    } = update;
+
     if !metadata.is_mutable {
         return Err(MetadataError::DataIsImmutable.into());
     }
@@ -1012,23 +1567,90 @@ fn command_metadata_update_account(
         data = Some(metadata.data);
     }
 
-    let minimum_balance_for_rent_exemption = config
-        .rpc_client
-        .get_minimum_balance_for_rent_exemption(MAX_METADATA_LEN)?;
-
-    let instructions = vec![update_metadata_accounts(
+    Ok(vec![update_metadata_accounts(
         metaplex_token_metadata::id(),
         metadata_address,
         update_authority,
         new_update_authority,
         data,
         primary_sale_happened,
-    )];
+    )])
+}
 
-    Ok(Some((
-        minimum_balance_for_rent_exemption,
-        vec![instructions],
-    )))
+/// Updates the metadata for each mint, continuing past individual failures.
+/// Mints are processed in groups of `concurrency`: each group's transactions are
+/// built and signed against a fresh blockhash and then sent and confirmed in parallel.
+fn command_metadata_update_batch(
+    config: &Config,
+    signers: &[Box<dyn Signer>],
+    mints: Vec<Pubkey>,
+    update_authority: Pubkey,
+    update: MetadataUpdate,
+    concurrency: usize,
+) -> CommandResult {
+    let mut results: Vec<UiMetadataUpdateResult> = Vec::with_capacity(mints.len());
+
+    for chunk in mints.chunks(concurrency) {
+        let recent_blockhash = match config.rpc_client.get_latest_blockhash() {
+            Ok(blockhash) => blockhash,
+            // Earlier chunks may already have landed, so record the failure for this
+            // chunk and carry on rather than losing the report.
+            Err(error) => {
+                results.extend(chunk.iter().map(|mint| {
+                    UiMetadataUpdateResult::from((*mint, Err(Error::from(error.to_string()))))
+                }));
+                continue;
+            }
+        };
+
+        let build_update_transaction = |mint: &Pubkey| -> Result<Transaction, Error> {
+            let (metadata_address, metadata) = Metadata::fetch_and_parse(config, mint)?;
+            let instructions = metadata_update_instructions(
+                config,
+                metadata_address,
+                metadata,
+                update_authority,
+                update.clone(),
+            )?;
+            build_transaction(config, signers, instructions, 0, recent_blockhash)
+        };
+        let transactions: Vec<(Pubkey, Result<Transaction, Error>)> = chunk
+            .iter()
+            .map(|mint| (*mint, build_update_transaction(mint)))
+            .collect();
+
+        let chunk_results: Vec<UiMetadataUpdateResult> = thread::scope(|scope| {
+            let handles: Vec<_> = transactions
+                .into_iter()
+                .map(|(mint, transaction)| {
+                    let handle = transaction.map(|transaction| {
+                        scope.spawn(move || {
+                            config.rpc_client.send_and_confirm_transaction(&transaction)
+                        })
+                    });
+                    (mint, handle)
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|(mint, handle)| {
+                    let result = handle.and_then(|h| h.join().unwrap().map_err(|e| e.into()));
+                    UiMetadataUpdateResult::from((mint, result))
+                })
+                .collect()
+        });
+        results.extend(chunk_results);
+    }
+
+    let cli_batch = CliMetadataUpdateBatch::from(results);
+    println!("{}", &config.output_format.formatted_string(&cli_batch));
+
+    if cli_batch.failed > 0 {
+        Err(format!("{} of {} updates failed.", cli_batch.failed, mints.len()).into())
+    } else {
+        Ok(None)
+    }
 }
 
 fn command_master_edition_create(
@@ -1302,6 +1924,7 @@ fn command_mint(
 mod cli_tests {
     use super::{get_app, get_creators_vec, validate_creator_shares};
     use clap::ErrorKind;
+    use solana_clap_utils::input_parsers::value_of;
     use solana_sdk::{
         pubkey::Pubkey,
         signer::{keypair::Keypair, Signer},
@@ -1449,15 +2072,236 @@ mod cli_tests {
         assert!(res.is_err());
         assert_eq!(res.unwrap_err().kind, ErrorKind::MissingRequiredArgument);
     }
+
+    #[test]
+    // It fails if --immutable is passed to an update command, which can't change is_mutable.
+    fn metadata_update_immutable() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        for (sub_command, target) in [
+            ("metadata-update", test_pubkey.to_string()),
+            ("metadata-update-batch", "mints.txt".to_string()),
+        ] {
+            let res = get_app().get_matches_from_safe(vec![
+                "testeroni",
+                sub_command,
+                &target,
+                "--name",
+                "yo yo",
+                "--immutable",
+            ]);
+            assert!(res.is_err());
+            assert_eq!(res.unwrap_err().kind, ErrorKind::UnknownArgument);
+        }
+    }
+
+    #[test]
+    // It still allows --immutable when creating metadata.
+    fn metadata_create_immutable() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let m = get_app().get_matches_from(vec![
+            "testeroni",
+            "metadata-create",
+            &test_pubkey.to_string(),
+            "--immutable",
+        ]);
+        let sub_m = m.subcommand_matches("metadata-create").unwrap();
+        assert!(sub_m.is_present("immutable"));
+    }
+
+    #[test]
+    // It fails if seller fee basis points are above 10000.
+    fn metadata_update_basis_points_too_high() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let res = get_app().get_matches_from_safe(vec![
+            "testeroni",
+            "metadata-update",
+            &test_pubkey.to_string(),
+            "--seller-fee-basis-points",
+            "10001",
+        ]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    // It passes if seller fee basis points are at the upper bound of 10000.
+    fn metadata_update_basis_points_max() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let m = get_app().get_matches_from(vec![
+            "testeroni",
+            "metadata-update",
+            &test_pubkey.to_string(),
+            "--seller-fee-basis-points",
+            "10000",
+        ]);
+        let sub_m = m.subcommand_matches("metadata-update").unwrap();
+        assert_eq!(
+            value_of::<u16>(sub_m, "seller_fee_basis_points").unwrap(),
+            10000
+        );
+    }
+
+    #[test]
+    fn metadata_update_batch() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let m = get_app().get_matches_from(vec![
+            "testeroni",
+            "metadata-update-batch",
+            "mints.txt",
+            "--concurrency",
+            "4",
+            "--new-update-authority",
+            &test_pubkey.to_string(),
+        ]);
+        let sub_m = m.subcommand_matches("metadata-update-batch").unwrap();
+        assert_eq!(sub_m.value_of("mints_file").unwrap(), "mints.txt");
+        assert_eq!(sub_m.value_of("concurrency").unwrap(), "4");
+    }
+
+    #[test]
+    // It fails if concurrency is less than one.
+    fn metadata_update_batch_concurrency_zero() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let res = get_app().get_matches_from_safe(vec![
+            "testeroni",
+            "metadata-update-batch",
+            "mints.txt",
+            "--concurrency",
+            "0",
+            "--new-update-authority",
+            &test_pubkey.to_string(),
+        ]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    // It fails if no update values are provided, same as metadata-update.
+    fn metadata_update_batch_no_args() {
+        let res = get_app().get_matches_from_safe(vec![
+            "testeroni",
+            "metadata-update-batch",
+            "mints.txt",
+        ]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn metadata_json() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let m = get_app().get_matches_from(vec![
+            "testeroni",
+            "metadata-json",
+            &test_pubkey.to_string(),
+        ]);
+        let sub_m = m.subcommand_matches("metadata-json").unwrap();
+        assert_eq!(sub_m.value_of("address").unwrap(), test_pubkey.to_string());
+    }
+
+    #[test]
+    // It fails if the address is not a valid pubkey.
+    fn metadata_json_pubkey() {
+        let res =
+            get_app().get_matches_from_safe(vec!["testeroni", "metadata-json", "bogus_pubkey"]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    // It fails if the mint address is missing.
+    fn metadata_pda_no_mint() {
+        let res = get_app().get_matches_from_safe(vec!["testeroni", "metadata-pda"]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    // It fails if the priority fee is not a whole number of micro-lamports.
+    fn metadata_update_priority_fee_invalid() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let res = get_app().get_matches_from_safe(vec![
+            "testeroni",
+            "metadata-update",
+            &test_pubkey.to_string(),
+            "--uri",
+            "ifps://testeroni",
+            "--priority-fee",
+            "0.5",
+        ]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    // It fails if the commitment level is not recognized.
+    fn commitment_invalid() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let res = get_app().get_matches_from_safe(vec![
+            "testeroni",
+            "metadata-info",
+            &test_pubkey.to_string(),
+            "--commitment",
+            "bogus",
+        ]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    // It fails for the deprecated commitment levels.
+    fn commitment_deprecated() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        for commitment in ["recent", "max", "root"] {
+            let res = get_app().get_matches_from_safe(vec![
+                "testeroni",
+                "metadata-info",
+                &test_pubkey.to_string(),
+                "--commitment",
+                commitment,
+            ]);
+            assert!(res.is_err());
+            assert_eq!(res.unwrap_err().kind, ErrorKind::InvalidValue);
+        }
+    }
+
+    #[test]
+    // It fails if both pretty and no-pretty are provided.
+    fn pretty_no_pretty_conflict() {
+        let test_pubkey: Pubkey = Keypair::new().pubkey();
+        let res = get_app().get_matches_from_safe(vec![
+            "testeroni",
+            "metadata-info",
+            &test_pubkey.to_string(),
+            "--output",
+            "json",
+            "--pretty",
+            "--no-pretty",
+        ]);
+        assert!(res.is_err());
+        assert_eq!(res.unwrap_err().kind, ErrorKind::ArgumentConflict);
+    }
 }
 
 #[cfg(test)]
 mod helper_tests {
-    use crate::MasterEditionCalc;
+    use crate::{
+        command_doctor, command_validate_address,
+        config::Config,
+        doctor_checks, is_valid_arweave_address,
+        output::{CliMetadataUpdateBatch, UiMetadataUpdate, UiMetadataUpdateResult},
+        parse_metadata_uri, parse_mints, with_compute_budget, Error, MasterEditionCalc,
+        MetadataUpdate,
+    };
 
     use super::FetchParse;
     use metaplex_token_metadata::state::Metadata;
-    use solana_sdk::pubkey::Pubkey;
+    use solana_cli_output::OutputFormat;
+    use solana_client::rpc_client::RpcClient;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction, instruction::Instruction, pubkey::Pubkey,
+        signature::Signature,
+    };
     use spl_token::state::Mint;
     use std::str::FromStr;
 
@@ -1498,4 +2342,181 @@ mod helper_tests {
         let calc_address = Mint::calc_master_edition(&mint_address);
         assert_eq!(calc_address, correct_address);
     }
+
+    #[test]
+    // It parses mints from either one address per line or a json list
+    fn mints_from_lines_and_json() {
+        let mint_address =
+            Pubkey::from_str("28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf").unwrap();
+        let lines = "28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf\n\n  28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf\n";
+        assert_eq!(
+            parse_mints(lines).unwrap(),
+            vec![mint_address, mint_address]
+        );
+
+        let json = r#"["28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf"]"#;
+        assert_eq!(parse_mints(json).unwrap(), vec![mint_address]);
+
+        assert!(parse_mints("bogus_pubkey").is_err());
+    }
+
+    #[test]
+    // It summarizes successes and failures in input order
+    fn metadata_update_batch_summary() {
+        let mint_address =
+            Pubkey::from_str("28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf").unwrap();
+        let signature = Signature::default();
+        let results: Vec<UiMetadataUpdateResult> = vec![
+            (mint_address, Ok(signature)),
+            (mint_address, Err(Error::from("blockhash not found"))),
+            (mint_address, Ok(signature)),
+        ]
+        .into_iter()
+        .map(UiMetadataUpdateResult::from)
+        .collect();
+
+        let cli_batch = CliMetadataUpdateBatch::from(results);
+        assert_eq!(cli_batch.updated, 2);
+        assert_eq!(cli_batch.failed, 1);
+        assert_eq!(cli_batch.results[0].signature, Some(signature.to_string()));
+        assert_eq!(cli_batch.results[0].error, None);
+        assert_eq!(cli_batch.results[1].signature, None);
+        assert_eq!(
+            cli_batch.results[1].error.as_deref(),
+            Some("blockhash not found")
+        );
+    }
+
+    #[test]
+    // It accepts 32 byte base64url addresses and rejects solana pubkeys
+    fn arweave_address_validation() {
+        assert!(is_valid_arweave_address("XqdiSjgo1a4oA-YLX65CkWkPrbs-5pvEuG79elDBDaQ").is_ok());
+        assert!(is_valid_arweave_address("28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf").is_err());
+        assert!(is_valid_arweave_address("not/base64url").is_err());
+    }
+
+    #[test]
+    // It trims null padding and only accepts http(s) uris
+    fn metadata_uri() {
+        let address = Pubkey::default();
+        let (uri, url) = parse_metadata_uri(&address, "https://arweave.net/abc\0\0\0").unwrap();
+        assert_eq!(uri, "https://arweave.net/abc");
+        assert_eq!(url.as_str(), "https://arweave.net/abc");
+        assert!(parse_metadata_uri(&address, "http://example.com/1.json").is_ok());
+
+        assert!(parse_metadata_uri(&address, "\0\0\0")
+            .unwrap_err()
+            .contains("does not have a uri"));
+        assert!(parse_metadata_uri(&address, "not a uri").is_err());
+        assert!(parse_metadata_uri(&address, "ipfs://tbd")
+            .unwrap_err()
+            .contains("ipfs://"));
+    }
+
+    // A config whose rpc client answers every request successfully with "succeeds"
+    // or fails every request with "fails".
+    fn mock_config(rpc_response: &str) -> Config {
+        Config {
+            rpc_client: RpcClient::new_mock(rpc_response.to_string()),
+            output_format: OutputFormat::Display,
+            fee_payer: Pubkey::default(),
+            default_keypair_path: String::new(),
+            dry_run: false,
+            priority_fee: None,
+            compute_unit_limit: None,
+        }
+    }
+
+    fn compute_budget_config(priority_fee: Option<u64>, compute_unit_limit: Option<u32>) -> Config {
+        Config {
+            priority_fee,
+            compute_unit_limit,
+            ..mock_config("succeeds")
+        }
+    }
+
+    #[test]
+    // It reports a keypair error as a failed check and skips the balance check
+    fn doctor_keypair_error() {
+        let config = mock_config("succeeds");
+        let checks = doctor_checks(&config, Some("No such file or directory".to_string()));
+        let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Keypair", "RPC"]);
+        assert!(!checks[0].passed);
+        assert!(checks[0].message.contains("No such file or directory"));
+        assert!(checks[1].passed);
+        assert!(command_doctor(&config, Some("No such file or directory".to_string())).is_err());
+    }
+
+    #[test]
+    // It passes every check when the rpc node answers and the fee payer has a balance
+    fn doctor_rpc_succeeds() {
+        let config = mock_config("succeeds");
+        let checks = doctor_checks(&config, None);
+        let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Keypair", "RPC", "Balance"]);
+        assert!(checks.iter().all(|c| c.passed));
+        assert!(command_doctor(&config, None).is_ok());
+    }
+
+    #[test]
+    // It returns an error when any check fails and skips the balance check without rpc
+    fn doctor_rpc_fails() {
+        let config = mock_config("fails");
+        let checks = doctor_checks(&config, None);
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].passed);
+        assert!(!checks[1].passed);
+        assert!(command_doctor(&config, None).is_err());
+    }
+
+    #[test]
+    // It serializes only the fields that were provided to the update
+    fn metadata_update_changed_fields() {
+        let update = MetadataUpdate {
+            name: Some("yo yo".to_string()),
+            ..MetadataUpdate::default()
+        };
+        let json = serde_json::to_value(UiMetadataUpdate::from(&update)).unwrap();
+        assert_eq!(json, serde_json::json!({ "name": "yo yo" }));
+    }
+
+    #[test]
+    // It fails only if the address is neither a solana pubkey nor an arweave address
+    fn validate_address() {
+        let config = mock_config("succeeds");
+        assert!(
+            command_validate_address(&config, "28TvgkwaFCNGttasXKoHc6eYGFJSTrBscmrNUEF83ZWf")
+                .is_ok()
+        );
+        assert!(
+            command_validate_address(&config, "XqdiSjgo1a4oA-YLX65CkWkPrbs-5pvEuG79elDBDaQ")
+                .is_ok()
+        );
+        assert!(command_validate_address(&config, "not/an/address").is_err());
+    }
+
+    #[test]
+    // It prepends the compute unit limit and then the price, and leaves instructions alone without either
+    fn compute_budget_instructions() {
+        let instructions = vec![Instruction::new_with_bytes(
+            metaplex_token_metadata::id(),
+            &[0],
+            vec![],
+        )];
+
+        let config = compute_budget_config(Some(10_000), Some(50_000));
+        let mut expected = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+            ComputeBudgetInstruction::set_compute_unit_price(10_000),
+        ];
+        expected.extend(instructions.clone());
+        assert_eq!(with_compute_budget(&config, instructions.clone()), expected);
+
+        let config = mock_config("succeeds");
+        assert_eq!(
+            with_compute_budget(&config, instructions.clone()),
+            instructions
+        );
+    }
 }
@@ -97,6 +97,247 @@ impl fmt::Display for CliMetadata {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliMetadataUpdate {
+    pub(crate) signature: String,
+    pub(crate) slot: Option<u64>,
+    pub(crate) mint: String,
+    pub(crate) updated: UiMetadataUpdate,
+}
+
+impl QuietDisplay for CliMetadataUpdate {}
+impl VerboseDisplay for CliMetadataUpdate {}
+impl fmt::Display for CliMetadataUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Signature: {}", self.signature)?;
+        writeln_name_value(
+            f,
+            "Slot:",
+            &self.slot.map(|s| s.to_string()).unwrap_or_default(),
+        )?;
+        writeln!(f, "Mint: {}", self.mint)?;
+        writeln!(f, "Updated:")?;
+        write!(f, "{}", self.updated)?;
+        Ok(())
+    }
+}
+
+// Only the fields provided to metadata-update are serialized.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UiMetadataUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) new_update_authority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) seller_fee_basis_points: Option<StringAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) creators: Option<Vec<UiCreator>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) primary_sale_happened: Option<bool>,
+}
+
+impl fmt::Display for UiMetadataUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(new_update_authority) = &self.new_update_authority {
+            writeln!(f, "  Update Authority: {}", new_update_authority)?;
+        }
+        if let Some(name) = &self.name {
+            writeln!(f, "  Name: {}", name)?;
+        }
+        if let Some(symbol) = &self.symbol {
+            writeln!(f, "  Symbol: {}", symbol)?;
+        }
+        if let Some(uri) = &self.uri {
+            writeln!(f, "  Uri: {}", uri)?;
+        }
+        if let Some(seller_fee_basis_points) = &self.seller_fee_basis_points {
+            writeln!(f, "  Seller Fee Basis Points: {}", seller_fee_basis_points)?;
+        }
+        if let Some(creators) = &self.creators {
+            writeln!(f, "  Creators: {}", creators.len())?;
+            for (i, c) in creators.iter().enumerate() {
+                writeln!(f, "  [{}] {}", i, c)?;
+            }
+        }
+        if let Some(primary_sale_happened) = self.primary_sale_happened {
+            writeln!(f, "  Primary Sale Happened: {}", primary_sale_happened)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliMetadataUpdateBatch {
+    pub(crate) updated: usize,
+    pub(crate) failed: usize,
+    pub(crate) results: Vec<UiMetadataUpdateResult>,
+}
+
+impl From<Vec<UiMetadataUpdateResult>> for CliMetadataUpdateBatch {
+    fn from(results: Vec<UiMetadataUpdateResult>) -> Self {
+        let failed = results.iter().filter(|r| r.error.is_some()).count();
+        Self {
+            updated: results.len() - failed,
+            failed,
+            results,
+        }
+    }
+}
+
+impl QuietDisplay for CliMetadataUpdateBatch {}
+impl VerboseDisplay for CliMetadataUpdateBatch {}
+impl fmt::Display for CliMetadataUpdateBatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for result in &self.results {
+            writeln!(f, "{}", result)?;
+        }
+        writeln!(f, "Updated: {}, Failed: {}", self.updated, self.failed)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UiMetadataUpdateResult {
+    pub(crate) mint: String,
+    pub(crate) signature: Option<String>,
+    pub(crate) error: Option<String>,
+}
+
+impl fmt::Display for UiMetadataUpdateResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.signature, &self.error) {
+            (_, Some(error)) => write!(f, "{}: failed: {}", self.mint, error),
+            (Some(signature), None) => write!(f, "{}: {}", self.mint, signature),
+            (None, None) => write!(f, "{}", self.mint),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliDoctor {
+    pub(crate) checks: Vec<UiDoctorCheck>,
+}
+
+impl QuietDisplay for CliDoctor {}
+impl VerboseDisplay for CliDoctor {}
+impl fmt::Display for CliDoctor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{}", check)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UiDoctorCheck {
+    pub(crate) name: String,
+    pub(crate) passed: bool,
+    pub(crate) message: String,
+}
+
+impl fmt::Display for UiDoctorCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if self.passed { "ok" } else { "FAILED" };
+        write!(f, "[{}] {}: {}", status, self.name, self.message)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliAddressValidation {
+    pub(crate) address: String,
+    pub(crate) solana_pubkey: UiAddressCheck,
+    pub(crate) arweave_address: UiAddressCheck,
+}
+
+impl QuietDisplay for CliAddressValidation {}
+impl VerboseDisplay for CliAddressValidation {}
+impl fmt::Display for CliAddressValidation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Address: {}", self.address)?;
+        writeln!(f, "Solana Pubkey: {}", self.solana_pubkey)?;
+        writeln!(f, "Arweave Address: {}", self.arweave_address)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct UiAddressCheck {
+    pub(crate) valid: bool,
+    pub(crate) error: Option<String>,
+}
+
+impl From<Result<(), String>> for UiAddressCheck {
+    fn from(result: Result<(), String>) -> Self {
+        Self {
+            valid: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
+
+impl fmt::Display for UiAddressCheck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.error {
+            None => write!(f, "valid"),
+            Some(error) => write!(f, "invalid ({})", error),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliMetadataPda {
+    pub(crate) mint: String,
+    pub(crate) metadata: String,
+    pub(crate) master_edition: String,
+}
+
+impl QuietDisplay for CliMetadataPda {}
+impl VerboseDisplay for CliMetadataPda {}
+impl fmt::Display for CliMetadataPda {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Mint: {}", self.mint)?;
+        writeln!(f, "Metadata: {}", self.metadata)?;
+        writeln!(f, "Master Edition: {}", self.master_edition)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CliMetadataJson {
+    pub(crate) uri: String,
+    pub(crate) json: serde_json::Value,
+}
+
+impl QuietDisplay for CliMetadataJson {}
+impl VerboseDisplay for CliMetadataJson {}
+impl fmt::Display for CliMetadataJson {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Uri: {}", self.uri)?;
+        writeln!(
+            f,
+            "{}",
+            serde_json::to_string_pretty(&self.json).map_err(|_| fmt::Error)?
+        )?;
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UiMetadata {